@@ -0,0 +1,10 @@
+// fuzz/fuzz_targets/lex.rs
+// Run with `cargo +nightly fuzz run lex`; `lex` must never panic or hang
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    let _ = lume::lexer::lex(&source, "fuzz");
+});
@@ -0,0 +1,38 @@
+// src/edition.rs
+
+// Language edition, decides which identifiers are reserved as keywords.
+// Keywords are only ever added in a new edition, so code written for an older
+// edition keeps lexing the same way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edition {
+    #[default]
+    E2025,
+    // Adds `in`, `orelse`, `break`, `yield` and `yields`
+    E2026,
+}
+
+impl Edition {
+    pub const LATEST: Edition = Edition::E2026;
+
+    // Look up an edition by the name used in `@edition:` pragmas, e.g. "2025"
+    pub fn from_name(name: &str) -> Option<Edition> {
+        match name {
+            "2025" => Some(Edition::E2025),
+            "2026" => Some(Edition::E2026),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Edition::E2025 => "2025",
+            Edition::E2026 => "2026",
+        }
+    }
+}
+
+impl std::fmt::Display for Edition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,143 @@
+// src/lexer/diff.rs
+// Structural diff of two token streams. Tokens are compared by value only, so edits
+// that merely move text around (whitespace, comments) produce no changes.
+
+use super::Token;
+use crate::span::Span;
+use std::ops::Range;
+
+// Above this many old x new token pairs in the changed region, skip the LCS table and
+// report the whole region as one replacement
+const MAX_TABLE_CELLS: usize = 4_000_000;
+
+// One hunk of difference; ranges index into the old and new token slices.
+// An empty `old` range is a pure insertion, an empty `new` range a pure deletion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenChange {
+    pub old: Range<usize>,
+    pub new: Range<usize>,
+}
+
+// Compute the changes turning `old` into `new`; an empty result means the streams
+// are token-for-token identical
+pub fn diff(old: &[(Token, Span)], new: &[(Token, Span)]) -> Vec<TokenChange> {
+    let old: Vec<&Token> = old.iter().map(|(tok, _)| tok).collect();
+    let new: Vec<&Token> = new.iter().map(|(tok, _)| tok).collect();
+
+    // Trim the common prefix and suffix, which is usually almost everything
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    if a.is_empty() && b.is_empty() {
+        return Vec::new();
+    }
+    let whole = TokenChange {
+        old: prefix..prefix + a.len(),
+        new: prefix..prefix + b.len(),
+    };
+    if a.is_empty() || b.is_empty() || (a.len() + 1) * (b.len() + 1) > MAX_TABLE_CELLS {
+        return vec![whole];
+    }
+
+    // lcs[i][j] = length of the longest common subsequence of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    // Walk the table, grouping consecutive unmatched tokens into hunks
+    let mut changes = Vec::new();
+    let mut hunk: Option<(usize, usize)> = None;
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            if let Some((old_start, new_start)) = hunk.take() {
+                changes.push(TokenChange {
+                    old: prefix + old_start..prefix + i,
+                    new: prefix + new_start..prefix + j,
+                });
+            }
+            i += 1;
+            j += 1;
+            continue;
+        }
+        hunk.get_or_insert((i, j));
+        if j == b.len() || (i < a.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if let Some((old_start, new_start)) = hunk {
+        changes.push(TokenChange {
+            old: prefix + old_start..prefix + a.len(),
+            new: prefix + new_start..prefix + b.len(),
+        });
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::lex;
+
+    fn changes(old: &str, new: &str) -> Vec<TokenChange> {
+        diff(&lex(old, "old").unwrap(), &lex(new, "new").unwrap())
+    }
+
+    #[test]
+    fn test_only_comments_changed() {
+        let old = "let x = 1; // one\nlet y = 2;";
+        let new = "/* header */\nlet x = 1;\n\n// changed\nlet   y = 2;";
+        assert!(changes(old, new).is_empty());
+    }
+
+    #[test]
+    fn test_insert_delete_replace() {
+        // let x = 1 ;  ->  let mut x = 1 ;
+        assert_eq!(
+            changes("let x = 1;", "let mut x = 1;"),
+            vec![TokenChange {
+                old: 1..1,
+                new: 1..2
+            }]
+        );
+        // Deleting the `mut` again
+        assert_eq!(
+            changes("let mut x = 1;", "let x = 1;"),
+            vec![TokenChange {
+                old: 1..2,
+                new: 1..1
+            }]
+        );
+        // Two separate literal edits give two hunks
+        assert_eq!(
+            changes("f(1, 2, 3);", "f(7, 2, 9);"),
+            vec![
+                TokenChange {
+                    old: 2..3,
+                    new: 2..3
+                },
+                TokenChange {
+                    old: 6..7,
+                    new: 6..7
+                },
+            ]
+        );
+    }
+}
@@ -1,46 +1,65 @@
 // src/lexer/mod.rs
 
-use crate::{error::LumeError, span::Span};
+use crate::{edition::Edition, error::LumeError, span::Span};
 use std::iter::Peekable;
 
+pub mod diff;
 pub mod token;
+pub use diff::{TokenChange, diff};
 pub use token::Token;
 
-// Main lexical analysis entry function, takes source code and filename, returns token sequence or error
+// Main lexical analysis entry function, takes source code and filename, returns token sequence or error.
+// Never panics and always terminates: any input yields either tokens ending in `Eof`,
+// with spans on char boundaries of `source`, or a `LumeError::Lexical`.
 pub fn lex(source: &str, file: &str) -> Result<Vec<(Token, Span)>, LumeError> {
-    let mut lexer = Lexer::new(source, file);
+    lex_with_edition(source, file, Edition::default())
+}
+
+// Same as `lex`, but with an explicit edition; an `@edition:` pragma in the file's
+// leading comments still takes precedence
+pub fn lex_with_edition(
+    source: &str,
+    file: &str,
+    edition: Edition,
+) -> Result<Vec<(Token, Span)>, LumeError> {
+    let mut lexer = Lexer::new(source, file, edition);
     lexer.lex()
 }
 
-// Core lexer structure, maintains state during lexical analysis
+// Core lexer structure, maintains state during lexical analysis.
+// Spans are byte offsets into the original source: a token runs from the offset
+// recorded by `start_token` up to the next unconsumed character.
 struct Lexer<'a> {
     source: &'a str,
     chars: Peekable<std::str::CharIndices<'a>>,
-    pos: usize,
+    token_start: usize,
     file: String,
+    edition: Edition,
+    // Set once an `@edition:` pragma has been applied; a second one is an error
+    edition_pragma: bool,
     tokens: Vec<(Token, Span)>,
 }
 
 impl<'a> Lexer<'a> {
     // Initialize lexer, handle possible shebang line
-    fn new(source: &'a str, file: &str) -> Self {
-        // If source starts with shebang, skip this line
-        let source = if source.starts_with("#!") {
-            // Find end position of first line
-            if let Some(idx) = source.find('\n') {
-                &source[idx + 1..]
-            } else {
-                ""
+    fn new(source: &'a str, file: &str, edition: Edition) -> Self {
+        let mut chars = source.char_indices().peekable();
+        // If source starts with shebang, skip this line (offsets stay relative to the full source)
+        if source.starts_with("#!") {
+            for (_, ch) in chars.by_ref() {
+                if ch == '\n' {
+                    break;
+                }
             }
-        } else {
-            source
-        };
+        }
 
         Self {
             source,
-            chars: source.char_indices().peekable(),
-            pos: 0,
+            chars,
+            token_start: 0,
             file: file.into(),
+            edition,
+            edition_pragma: false,
             tokens: Vec::new(),
         }
     }
@@ -48,7 +67,7 @@ impl<'a> Lexer<'a> {
     // Main lexical analysis loop, process characters one by one and generate tokens
     fn lex(&mut self) -> Result<Vec<(Token, Span)>, LumeError> {
         while let Some((start, ch)) = self.chars.next() {
-            self.pos = start;
+            self.start_token(start);
             match ch {
                 // Skip whitespace characters
                 ' ' | '\t' | '\n' | '\r' => continue,
@@ -56,7 +75,6 @@ impl<'a> Lexer<'a> {
                 // Number literal processing: must ensure it's not part of an identifier
                 '0'..='9' => {
                     let (num_token, end) = self.read_number(start)?;
-                    self.pos = end;
                     // Advance character iterator to end position
                     while let Some(&(pos, _)) = self.chars.peek() {
                         if pos < end {
@@ -65,44 +83,37 @@ impl<'a> Lexer<'a> {
                             break;
                         }
                     }
-                    self.push_token((num_token, self.span(start, end)));
+                    self.end_token(num_token);
                 }
 
                 // Identifier and keyword processing
                 'a'..='z' | 'A'..='Z' | '_' | '\u{80}'..='\u{10FFFF}' => {
-                    let ident_start = start;
-                    let ident = self.read_ident(ch, start)?;
+                    let ident = self.read_ident(ch);
                     match self.peek() {
                         // Handle prefixed strings like r"..." or sql"..."
                         Some('"') => {
                             self.chars.next(); // Consume quote
-                            let (content, end) = self.read_string_content(false)?; // Raw strings don't need escaping
-                            self.pos = end;
-                            self.push_token((
-                                Token::PrefixedStr(ident, content),
-                                self.span(ident_start, end),
-                            ));
+                            let content = self.read_string_content(false)?; // Raw strings don't need escaping
+                            self.end_token(Token::PrefixedStr(ident, content));
                         }
                         // Handle prefixed character literals like r'a' or sql'\n'
                         Some('\'') => {
                             self.chars.next(); // Consume opening quote
-                            let (token, end) = self.read_prefixed_char(ident, ident_start)?;
-                            self.pos = end;
-                            self.push_token((token, self.span(ident_start, end)));
+                            let token = self.read_prefixed_char(ident)?;
+                            self.end_token(token);
                         }
                         // Regular identifier or keyword
                         _ => {
-                            let token = token::keyword_or_ident(&ident);
-                            self.push_token((token, self.span(ident_start, self.pos)));
+                            let token = token::keyword_or_ident(&ident, self.edition);
+                            self.end_token(token);
                         }
                     }
                 }
 
                 // String literal processing
                 '"' => {
-                    let (content, end) = self.read_string_content(true)?; // Allow escaping
-                    self.pos = end;
-                    self.push_token((Token::Str(content), self.span(start, end)));
+                    let content = self.read_string_content(true)?; // Allow escaping
+                    self.end_token(Token::Str(content));
                 }
 
                 // Character literal or lifetime processing
@@ -112,33 +123,42 @@ impl<'a> Lexer<'a> {
                     match chars_ahead.next() {
                         Some((_, first_ch)) => {
                             if first_ch.is_alphabetic() || first_ch == '_' {
-                                // Could be character literal like 'a' or lifetime like 'static
-                                match chars_ahead.next() {
-                                    Some((_, '\'')) => {
-                                        // This is a character literal like 'a'
-                                        let token = self.read_char_literal(start)?;
-                                        self.push_token((token, self.span(start, self.pos)));
-                                    }
-                                    Some(_) => {
-                                        // This is a lifetime like 'static
-                                        self.chars.next();
-                                        let ident = self.read_ident(first_ch, start + 1)?;
-                                        self.push_token((
-                                            Token::Lifetime(ident),
-                                            self.span(start, self.pos),
-                                        ));
-                                    }
-                                    None => {
-                                        return Err(LumeError::Lexical {
-                                            msg: "unexpected end of input after quote".into(),
-                                            span: self.span(start, start + 1),
-                                        });
+                                // Could be character literal like 'a' or lifetime like 'static;
+                                // a quote right after the identifier run means a character
+                                // literal, including the invalid multi-character 'ab'
+                                let is_char = loop {
+                                    match chars_ahead.next() {
+                                        Some((_, '\'')) => break true,
+                                        Some((_, ch)) if is_ident_continue(ch) => continue,
+                                        // What can follow a lifetime: `&'a T`, `<'a, 'b>`, `'a:`
+                                        Some((_, ch))
+                                            if ch.is_whitespace() || ",>)]}:;+=|".contains(ch) =>
+                                        {
+                                            break false;
+                                        }
+                                        None => break false,
+                                        // Anything else ('a\n', 'a"') is a malformed character
+                                        // literal if a quote closes it on the same line
+                                        Some(_) => {
+                                            break chars_ahead
+                                                .take_while(|&(_, ch)| ch != '\n')
+                                                .any(|(_, ch)| ch == '\'');
+                                        }
                                     }
+                                };
+                                if is_char {
+                                    let token = self.read_char_literal()?;
+                                    self.end_token(token);
+                                } else {
+                                    // This is a lifetime like 'static
+                                    self.chars.next();
+                                    let ident = self.read_ident(first_ch);
+                                    self.end_token(Token::Lifetime(ident));
                                 }
                             } else {
                                 // This must be a character literal like '5', '\n' etc.
-                                let token = self.read_char_literal(start)?;
-                                self.push_token((token, self.span(start, self.pos)));
+                                let token = self.read_char_literal()?;
+                                self.end_token(token);
                             }
                         }
                         None => {
@@ -152,20 +172,17 @@ impl<'a> Lexer<'a> {
 
                 // Operator processing
                 '=' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::EqEq, self.span(start, start + 2)));
-                    } else if self.peek() == Some('>') {
-                        self.chars.next();
-                        self.push_token((Token::FatArrow, self.span(start, start + 2)));
+                    if self.eat('=') {
+                        self.end_token(Token::EqEq);
+                    } else if self.eat('>') {
+                        self.end_token(Token::FatArrow);
                     } else {
-                        self.push_token((Token::Eq, self.span(start, start + 1)));
+                        self.end_token(Token::Eq);
                     }
                 }
                 '!' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::Neq, self.span(start, start + 2)));
+                    if self.eat('=') {
+                        self.end_token(Token::Neq);
                     } else {
                         return Err(LumeError::Lexical {
                             msg: "unexpected '!'; logical NOT is written as 'not'".into(),
@@ -174,146 +191,134 @@ impl<'a> Lexer<'a> {
                     }
                 }
                 '<' => {
-                    match self.peek() {
-                        Some('<') => {
-                            self.chars.next(); // Consume second '<'
-                            if self.peek() == Some('=') {
-                                self.chars.next();
-                                self.push_token((Token::ShlEq, self.span(start, start + 3)));
-                            } else {
-                                self.push_token((Token::Shl, self.span(start, start + 2)));
-                            }
-                        }
-                        Some('=') => {
-                            self.chars.next();
-                            self.push_token((Token::Le, self.span(start, start + 2)));
-                        }
-                        _ => {
-                            self.push_token((Token::Lt, self.span(start, start + 1)));
+                    if self.eat('<') {
+                        if self.eat('=') {
+                            self.end_token(Token::ShlEq);
+                        } else {
+                            self.end_token(Token::Shl);
                         }
+                    } else if self.eat('=') {
+                        self.end_token(Token::Le);
+                    } else {
+                        self.end_token(Token::Lt);
                     }
                 }
                 '>' => {
-                    match self.peek() {
-                        Some('>') => {
-                            self.chars.next(); // Consume second '>'
-                            if self.peek() == Some('=') {
-                                self.chars.next();
-                                self.push_token((Token::ShrEq, self.span(start, start + 3)));
-                            } else {
-                                self.push_token((Token::Shr, self.span(start, start + 2)));
-                            }
-                        }
-                        Some('=') => {
-                            self.chars.next();
-                            self.push_token((Token::Ge, self.span(start, start + 2)));
-                        }
-                        _ => {
-                            self.push_token((Token::Gt, self.span(start, start + 1)));
+                    if self.eat('>') {
+                        if self.eat('=') {
+                            self.end_token(Token::ShrEq);
+                        } else {
+                            self.end_token(Token::Shr);
                         }
+                    } else if self.eat('=') {
+                        self.end_token(Token::Ge);
+                    } else {
+                        self.end_token(Token::Gt);
                     }
                 }
                 '+' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::PlusEq, self.span(start, start + 2)));
+                    if self.eat('=') {
+                        self.end_token(Token::PlusEq);
                     } else {
-                        self.push_token((Token::Plus, self.span(start, start + 1)));
+                        self.end_token(Token::Plus);
                     }
                 }
                 '-' => {
-                    if self.peek() == Some('>') {
-                        self.chars.next();
-                        self.push_token((Token::Arrow, self.span(start, start + 2)));
-                    } else if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::MinusEq, self.span(start, start + 2)));
+                    if self.eat('>') {
+                        self.end_token(Token::Arrow);
+                    } else if self.eat('=') {
+                        self.end_token(Token::MinusEq);
                     } else {
-                        self.push_token((Token::Minus, self.span(start, start + 1)));
+                        self.end_token(Token::Minus);
                     }
                 }
                 '*' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::StarEq, self.span(start, start + 2)));
+                    if self.eat('=') {
+                        self.end_token(Token::StarEq);
                     } else {
-                        self.push_token((Token::Star, self.span(start, start + 1)));
+                        self.end_token(Token::Star);
                     }
                 }
                 '/' => {
-                    if let Some(next) = self.peek() {
-                        match next {
-                            '/' => {
-                                self.chars.next(); // Consume second '/'
-                                if let Some(third) = self.peek() {
-                                    if third == '/' {
-                                        self.chars.next();
-                                    }
-                                }
-                                self.skip_line_comment();
-                                continue;
-                            }
-                            '*' => {
-                                self.chars.next(); // Consume '*'
-                                self.skip_block_comment()?;
-                                continue;
-                            }
-                            '=' => {
-                                self.chars.next();
-                                self.push_token((Token::SlashEq, self.span(start, start + 2)));
-                            }
-                            _ => {
-                                self.push_token((Token::Slash, self.span(start, start + 1)));
-                            }
+                    if self.eat('/') {
+                        // Doc comments start with a third '/'
+                        let is_doc = self.eat('/');
+                        let comment = self.read_line_comment();
+                        // Pragmas are only honoured in plain comments before the first token
+                        if !is_doc && self.tokens.is_empty() {
+                            self.apply_pragma(&comment, start)?;
                         }
+                    } else if self.eat('*') {
+                        self.skip_block_comment()?;
+                    } else if self.eat('=') {
+                        self.end_token(Token::SlashEq);
                     } else {
-                        self.push_token((Token::Slash, self.span(start, start + 1)));
+                        self.end_token(Token::Slash);
                     }
                 }
                 '%' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::PercentEq, self.span(start, start + 2)));
+                    if self.eat('=') {
+                        self.end_token(Token::PercentEq);
                     } else {
-                        self.push_token((Token::Percent, self.span(start, start + 1))); // Need to add Percent token
+                        self.end_token(Token::Percent);
                     }
                 }
                 '&' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::AmpEq, self.span(start, start + 2)));
+                    if self.eat('&') {
+                        return Err(LumeError::Lexical {
+                            msg: "unexpected '&&'; logical AND is written as 'and'".into(),
+                            span: self.span_from(start),
+                        });
+                    } else if self.eat('=') {
+                        self.end_token(Token::AmpEq);
                     } else {
-                        self.push_token((Token::Amp, self.span(start, start + 1)));
+                        self.end_token(Token::Amp);
                     }
                 }
                 '|' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::PipeEq, self.span(start, start + 2)));
+                    if self.eat('|') {
+                        return Err(LumeError::Lexical {
+                            msg: "unexpected '||'; logical OR is written as 'or'".into(),
+                            span: self.span_from(start),
+                        });
+                    } else if self.eat('=') {
+                        self.end_token(Token::PipeEq);
                     } else {
-                        self.push_token((Token::Pipe, self.span(start, start + 1)));
+                        self.end_token(Token::Pipe);
                     }
                 }
                 '^' => {
-                    if self.peek() == Some('=') {
-                        self.chars.next();
-                        self.push_token((Token::CaretEq, self.span(start, start + 2)));
+                    if self.eat('=') {
+                        self.end_token(Token::CaretEq);
+                    } else {
+                        self.end_token(Token::Caret);
+                    }
+                }
+                '~' => self.end_token(Token::Tilde),
+                '(' => self.end_token(Token::LParen),
+                ')' => self.end_token(Token::RParen),
+                '{' => self.end_token(Token::LBrace),
+                '}' => self.end_token(Token::RBrace),
+                '[' => self.end_token(Token::LBracket),
+                ']' => self.end_token(Token::RBracket),
+                ';' => self.end_token(Token::Semicolon),
+                ',' => self.end_token(Token::Comma),
+                ':' => self.end_token(Token::Colon),
+                '.' => {
+                    if self.eat('.') {
+                        if self.eat('.') {
+                            self.end_token(Token::Ellipsis);
+                        } else if self.eat('=') {
+                            self.end_token(Token::DotDotEq);
+                        } else {
+                            self.end_token(Token::DotDot);
+                        }
                     } else {
-                        self.push_token((Token::Caret, self.span(start, start + 1)));
+                        self.end_token(Token::Dot);
                     }
                 }
-                '(' => self.push_token((Token::LParen, self.span(start, start + 1))),
-                ')' => self.push_token((Token::RParen, self.span(start, start + 1))),
-                '{' => self.push_token((Token::LBrace, self.span(start, start + 1))),
-                '}' => self.push_token((Token::RBrace, self.span(start, start + 1))),
-                '[' => self.push_token((Token::LBracket, self.span(start, start + 1))),
-                ']' => self.push_token((Token::RBracket, self.span(start, start + 1))),
-                ';' => self.push_token((Token::Semicolon, self.span(start, start + 1))),
-                ',' => self.push_token((Token::Comma, self.span(start, start + 1))),
-                ':' => self.push_token((Token::Colon, self.span(start, start + 1))),
-                '.' => self.push_token((Token::Dot, self.span(start, start + 1))),
-                '?' => self.push_token((Token::Question, self.span(start, start + 1))),
-                '@' => self.push_token((Token::At, self.span(start, start + 1))),
+                '?' => self.end_token(Token::Question),
+                '@' => self.end_token(Token::At),
                 _ => {
                     return Err(LumeError::Lexical {
                         msg: format!("unexpected character: '{}'", ch),
@@ -322,20 +327,20 @@ impl<'a> Lexer<'a> {
                 }
             }
         }
-        self.push_token((Token::Eof, self.span(self.source.len(), self.source.len())));
+        let end = self.source.len();
+        self.tokens.push((Token::Eof, self.span(end, end)));
         Ok(std::mem::take(&mut self.tokens))
     }
 
-    // Add token to token vector
-    fn push_token(&mut self, token: (Token, Span)) {
-        // match token.0 {
-        //     Token::Let | Token::Ident(_) => {
-        //         dbg!(&token);
-        //     }
-        //     _ => {}
-        // }
-        dbg!(&token);
-        self.tokens.push(token);
+    // Record the byte offset where the current token begins
+    fn start_token(&mut self, start: usize) {
+        self.token_start = start;
+    }
+
+    // Finish the current token: its span runs up to the next unconsumed character
+    fn end_token(&mut self, token: Token) {
+        let span = self.span_from(self.token_start);
+        self.tokens.push((token, span));
     }
 
     // Create span object representing source code range
@@ -343,19 +348,42 @@ impl<'a> Lexer<'a> {
         Span::new(start, end, &self.file)
     }
 
+    // Span from `start` up to the next unconsumed character
+    fn span_from(&mut self, start: usize) -> Span {
+        let end = self.offset();
+        self.span(start, end)
+    }
+
+    // Byte offset of the next unconsumed character, or the end of input
+    fn offset(&mut self) -> usize {
+        match self.chars.peek() {
+            Some(&(idx, _)) => idx,
+            None => self.source.len(),
+        }
+    }
+
     // Peek at next character without consuming it
     fn peek(&mut self) -> Option<char> {
         self.chars.peek().map(|(_, ch)| *ch)
     }
 
+    // Consume the next character if it is `expected`
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
     // --- Number parsing ---
     // Parse number literals, including integers, floats and numbers in different bases
     fn read_number(&mut self, start: usize) -> Result<(Token, usize), LumeError> {
         let mut num_str = String::new();
-        let chars: Vec<(usize, char)> = self.source[start..]
-            .char_indices()
-            .map(|(i, c)| (start + i, c))
-            .collect();
+        // Number literals are pure ASCII, so scan bytes in place rather than collecting
+        // the rest of the source; non-ASCII bytes never match any digit class below
+        let chars = &self.source.as_bytes()[start..];
 
         let mut i = 0;
         let mut base = 10;
@@ -363,24 +391,24 @@ impl<'a> Lexer<'a> {
         let mut has_exp = false;
 
         // Check for base prefixes
-        if chars.get(0).map(|&(_, c)| c) == Some('0') && chars.len() > 1 {
-            match chars.get(1).map(|&(_, c)| c) {
+        if chars.first() == Some(&b'0') && chars.len() > 1 {
+            match chars.get(1).map(|&b| b as char) {
                 Some('x') | Some('X') => {
                     base = 16;
                     num_str.push('0');
-                    num_str.push(chars[1].1);
+                    num_str.push(chars[1] as char);
                     i = 2;
                 }
                 Some('b') | Some('B') => {
                     base = 2;
                     num_str.push('0');
-                    num_str.push(chars[1].1);
+                    num_str.push(chars[1] as char);
                     i = 2;
                 }
                 Some('o') | Some('O') => {
                     base = 8;
                     num_str.push('0');
-                    num_str.push(chars[1].1);
+                    num_str.push(chars[1] as char);
                     i = 2;
                 }
                 _ => {
@@ -388,7 +416,7 @@ impl<'a> Lexer<'a> {
                     i = 1;
                     // Continue decimal parsing
                     while i < chars.len() {
-                        let (_, ch) = chars[i];
+                        let ch = chars[i] as char;
                         if ch.is_ascii_digit() || ch == '_' {
                             num_str.push(ch);
                             i += 1;
@@ -401,7 +429,7 @@ impl<'a> Lexer<'a> {
         } else {
             // Normal decimal start
             while i < chars.len() {
-                let (_, ch) = chars[i];
+                let ch = chars[i] as char;
                 if ch.is_ascii_digit() || ch == '_' {
                     num_str.push(ch);
                     i += 1;
@@ -422,7 +450,7 @@ impl<'a> Lexer<'a> {
             };
             let mut has_digit = false;
             while i < chars.len() {
-                let (_, ch) = chars[i];
+                let ch = chars[i] as char;
                 if ch == '_' {
                     num_str.push(ch);
                     i += 1;
@@ -444,14 +472,14 @@ impl<'a> Lexer<'a> {
         }
 
         // Check for decimal point (float)
-        if i < chars.len() && chars[i].1 == '.' {
+        if i < chars.len() && chars[i] == b'.' {
             let next_i = i + 1;
-            if next_i < chars.len() && chars[next_i].1.is_ascii_digit() {
+            if next_i < chars.len() && chars[next_i].is_ascii_digit() {
                 has_dot = true;
                 num_str.push('.');
                 i = next_i;
                 while i < chars.len() {
-                    let (_, ch) = chars[i];
+                    let ch = chars[i] as char;
                     if ch.is_ascii_digit() || ch == '_' {
                         num_str.push(ch);
                         i += 1;
@@ -463,17 +491,17 @@ impl<'a> Lexer<'a> {
         }
 
         // Check for exponent
-        if i < chars.len() && matches!(chars[i].1, 'e' | 'E') {
+        if i < chars.len() && matches!(chars[i], b'e' | b'E') {
             has_exp = true;
-            num_str.push(chars[i].1);
+            num_str.push(chars[i] as char);
             i += 1;
-            if i < chars.len() && matches!(chars[i].1, '+' | '-') {
-                num_str.push(chars[i].1);
+            if i < chars.len() && matches!(chars[i], b'+' | b'-') {
+                num_str.push(chars[i] as char);
                 i += 1;
             }
             let mut has_digit = false;
             while i < chars.len() {
-                let (_, ch) = chars[i];
+                let ch = chars[i] as char;
                 if ch.is_ascii_digit() || ch == '_' {
                     num_str.push(ch);
                     has_digit = true;
@@ -504,7 +532,14 @@ impl<'a> Lexer<'a> {
                 msg: "invalid float literal".into(),
                 span: self.span(start, start + i),
             })?;
-            return Ok((Token::Float(val), start + i));
+            // Out-of-range literals like 1e999 would otherwise silently become infinity
+            if val.is_infinite() {
+                return Err(LumeError::Lexical {
+                    msg: "float literal out of range for f64".into(),
+                    span: self.span(start, start + i),
+                });
+            }
+            Ok((Token::Float(val), start + i))
         } else {
             // Handle regular decimal and prefixed numbers
             let (value_str, parse_base) = if base != 10 {
@@ -515,63 +550,70 @@ impl<'a> Lexer<'a> {
                 (&clean[..], 10)
             };
 
-            let val = i64_from_radix(value_str, parse_base).map_err(|_| LumeError::Lexical {
-                msg: "integer literal too large".into(),
-                span: self.span(start, start + i),
-            })?;
-            return Ok((Token::Int(val), start + i));
+            let token = match i64_from_radix(value_str, parse_base) {
+                Ok(val) => Token::Int(val),
+                // Only valid under a unary minus; checking that is left to the parser
+                Err(_) if is_i64_min_magnitude(value_str, parse_base) => Token::IntMinMagnitude,
+                Err(_) => {
+                    return Err(LumeError::Lexical {
+                        msg: "integer literal too large".into(),
+                        span: self.span(start, start + i),
+                    });
+                }
+            };
+            Ok((token, start + i))
         }
     }
 
     // --- Identifier processing ---
-    // Read identifier or keyword starting with given character
-    fn read_ident(&mut self, first: char, start: usize) -> Result<String, LumeError> {
+    // Read identifier or keyword starting with given (already consumed) character
+    fn read_ident(&mut self, first: char) -> String {
         let mut ident = first.to_string();
-        let mut len = first.len_utf8();
-        while let Some((_, ch)) = self.chars.peek() {
-            if ch.is_alphanumeric() || *ch == '_' || *ch > '\u{7F}' {
-                ident.push(*ch);
-                len += ch.len_utf8();
+        while let Some(&(_, ch)) = self.chars.peek() {
+            if is_ident_continue(ch) {
+                ident.push(ch);
                 self.chars.next();
             } else {
                 break;
             }
         }
-        self.pos = start + len;
-        Ok(ident)
+        ident
     }
 
     // --- String processing ---
-    // Read string content, decide whether to process escape sequences based on allow_escape parameter
-    fn read_string_content(&mut self, allow_escape: bool) -> Result<(String, usize), LumeError> {
+    // Read string content after the opening quote, decide whether to process escape
+    // sequences based on allow_escape parameter; consumes the closing quote
+    fn read_string_content(&mut self, allow_escape: bool) -> Result<String, LumeError> {
         let mut s = String::new();
-        let start_pos = self.pos;
         loop {
             match self.chars.next() {
-                Some((_, '"')) => {
-                    return Ok((s, self.pos + 1));
-                }
-                Some((idx, ch)) => {
-                    if allow_escape && ch == '\\' {
-                        let escaped = self.read_escape()?;
-                        s.push(escaped);
+                Some((_, '"')) => return Ok(s),
+                Some((_, '\\')) if allow_escape => {
+                    if matches!(self.peek(), Some('\n' | '\r')) {
+                        // Line continuation: drop the newline and the next line's leading whitespace
+                        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+                            self.chars.next();
+                        }
                     } else {
-                        s.push(ch);
+                        let escaped = self.read_escape(false)?;
+                        s.push(escaped);
                     }
-                    self.pos = idx;
                 }
+                Some((_, ch)) => s.push(ch),
                 None => {
                     return Err(LumeError::Lexical {
                         msg: "unterminated string literal".into(),
-                        span: self.span(start_pos, self.source.len()),
+                        span: self.span(self.token_start, self.source.len()),
                     });
                 }
             }
         }
     }
 
-    // Parse escape sequences in strings and character literals
-    fn read_escape(&mut self) -> Result<char, LumeError> {
+    // Parse escape sequences in strings and character literals; the backslash is already consumed.
+    // `in_char` only affects the wording of diagnostics.
+    fn read_escape(&mut self, in_char: bool) -> Result<char, LumeError> {
+        let escape_start = self.offset() - 1;
         match self.chars.next() {
             Some((_, 'n')) => Ok('\n'),
             Some((_, 'r')) => Ok('\r'),
@@ -579,155 +621,204 @@ impl<'a> Lexer<'a> {
             Some((_, '\\')) => Ok('\\'),
             Some((_, '"')) => Ok('"'),
             Some((_, '\'')) => Ok('\''),
+            Some((_, '0')) => Ok('\0'),
+            Some((_, '\n' | '\r')) => Err(LumeError::Lexical {
+                msg: "line continuation is only allowed in string literals".into(),
+                span: self.span(escape_start, escape_start + 1),
+            }),
+            Some((_, 'x')) => {
+                // Exactly two hex digits, limited to ASCII like Rust's \xNN
+                let mut value = 0;
+                for _ in 0..2 {
+                    match self.chars.peek() {
+                        Some(&(_, ch)) if ch.is_ascii_hexdigit() => {
+                            self.chars.next();
+                            value = value * 16 + ch.to_digit(16).unwrap_or(0);
+                        }
+                        _ => {
+                            return Err(LumeError::Lexical {
+                                msg: "\\x escape must have exactly two hex digits".into(),
+                                span: self.span_from(escape_start),
+                            });
+                        }
+                    }
+                }
+                if value > 0x7F {
+                    let (literal, suggestion) = if in_char {
+                        ("character", format!("'\\u{{{:X}}}'", value))
+                    } else {
+                        ("string", format!("\"\\u{{{:X}}}\"", value))
+                    };
+                    return Err(LumeError::Lexical {
+                        msg: format!(
+                            "\\x{:X} is out of range in a {} literal (\\x00-\\x7F); use {} for U+{:04X}",
+                            value, literal, suggestion, value
+                        ),
+                        span: self.span_from(escape_start),
+                    });
+                }
+                Ok(value as u8 as char)
+            }
             Some((_, 'u')) => {
-                if self.peek() != Some('{') {
+                if !self.eat('{') {
                     return Err(LumeError::Lexical {
                         msg: "expected '{' after \\u".into(),
-                        span: self.span(self.pos, self.pos + 1),
+                        span: self.span_from(escape_start),
                     });
                 }
-                self.chars.next(); // Consume '{'
 
                 let mut hex = String::new();
-                while let Some((_, ch)) = self.chars.peek() {
-                    if *ch == '}' {
+                while let Some(&(idx, ch)) = self.chars.peek() {
+                    if ch == '}' {
                         self.chars.next();
                         break;
                     }
                     if ch.is_ascii_hexdigit() {
-                        hex.push(*ch);
+                        hex.push(ch);
                         self.chars.next();
                     } else {
                         return Err(LumeError::Lexical {
                             msg: "invalid hex digit in \\u{...}".into(),
-                            span: self.span(self.pos, self.pos + 1),
+                            span: self.span(idx, idx + ch.len_utf8()),
                         });
                     }
                 }
                 if hex.is_empty() || hex.len() > 6 {
                     return Err(LumeError::Lexical {
                         msg: "unicode escape must have 1-6 hex digits".into(),
-                        span: self.span(self.pos, self.pos + 1),
+                        span: self.span_from(escape_start),
                     });
                 }
                 let codepoint = u32::from_str_radix(&hex, 16).map_err(|_| LumeError::Lexical {
                     msg: "invalid unicode escape".into(),
-                    span: self.span(self.pos, self.pos + 1),
+                    span: self.span_from(escape_start),
                 })?;
                 if let Some(ch) = std::char::from_u32(codepoint) {
                     Ok(ch)
                 } else {
                     Err(LumeError::Lexical {
                         msg: "invalid unicode codepoint".into(),
-                        span: self.span(self.pos, self.pos + 1),
+                        span: self.span_from(escape_start),
                     })
                 }
             }
             Some((_, ch)) => Err(LumeError::Lexical {
                 msg: format!("unknown escape sequence \\{}", ch),
-                span: self.span(self.pos, self.pos + 1),
+                span: self.span_from(escape_start),
             }),
             None => Err(LumeError::Lexical {
                 msg: "unterminated escape sequence".into(),
-                span: self.span(self.pos, self.source.len()),
+                span: self.span(escape_start, self.source.len()),
             }),
         }
     }
 
     // --- Character literals ---
-    // Parse standard character literals
-    fn read_char_literal(&mut self, start: usize) -> Result<Token, LumeError> {
+    // Parse standard character literals; opening quote already consumed
+    fn read_char_literal(&mut self) -> Result<Token, LumeError> {
+        let ch = self.read_char_body()?;
+        Ok(Token::Char(ch))
+    }
+
+    // --- Prefixed character literals ---
+    // Parse prefixed character literals (e.g. r'a', sql'\n'); opening quote already consumed
+    fn read_prefixed_char(&mut self, prefix: String) -> Result<Token, LumeError> {
+        let ch = self.read_char_body()?;
+        Ok(Token::PrefixedChar(prefix, ch))
+    }
+
+    // Read the single character of a char literal and its closing quote
+    fn read_char_body(&mut self) -> Result<char, LumeError> {
         match self.chars.next() {
             None => Err(LumeError::Lexical {
                 msg: "unterminated character literal".into(),
-                span: self.span(start, self.source.len()),
+                span: self.span(self.token_start, self.source.len()),
             }),
             Some((_, '\'')) => Err(LumeError::Lexical {
                 msg: "empty character literal".into(),
-                span: self.span(start, start + 2),
+                span: self.span_from(self.token_start),
             }),
-            Some((_, ch)) => {
-                let ch = if ch == '\\' { self.read_escape()? } else { ch };
-
-                if self.peek() != Some('\'') {
-                    return Err(LumeError::Lexical {
-                        msg: "character literal must contain exactly one character".into(),
-                        span: self.span(start, self.pos + 1),
-                    });
-                }
-
-                self.chars.next(); // Consume closing quote
-                // Correctly update position
-                self.pos = if let Some((next_pos, _)) = self.chars.peek() {
-                    next_pos.clone()
+            Some((content_start, ch)) => {
+                let ch = if ch == '\\' {
+                    self.read_escape(true)?
                 } else {
-                    self.source.len()
+                    ch
                 };
-
-                Ok(Token::Char(ch))
-            }
-        }
-    }
-
-    // --- Prefixed character literals ---
-    // Parse prefixed character literals (e.g. r'a', sql'\n')
-    fn read_prefixed_char(
-        &mut self,
-        prefix: String,
-        prefix_start: usize,
-    ) -> Result<(Token, usize), LumeError> {
-        // Opening quote already consumed
-        let ch = match self.chars.next() {
-            None => {
-                return Err(LumeError::Lexical {
-                    msg: "unterminated character literal".into(),
-                    span: self.span(prefix_start, self.source.len()),
-                });
-            }
-            Some((_, '\'')) => {
-                return Err(LumeError::Lexical {
-                    msg: "empty character literal".into(),
-                    span: self.span(prefix_start, prefix_start + 2),
-                });
-            }
-            Some((content_start, c)) => {
-                let c = if c == '\\' { self.read_escape()? } else { c };
-                // Verify it's a valid Unicode scalar value (not a surrogate pair)
-                let cp = c as u32;
-                if cp >= 0xD800 && cp <= 0xDFFF {
-                    return Err(LumeError::Lexical {
-                        msg: "character literal contains invalid Unicode surrogate".into(),
-                        span: self.span(content_start, self.pos),
-                    });
+                if self.eat('\'') {
+                    return Ok(ch);
                 }
-                if self.peek() != Some('\'') {
-                    return Err(LumeError::Lexical {
-                        msg: "character literal must contain exactly one character".into(),
-                        span: self.span(content_start, self.pos + 1),
-                    });
+                // Scan to the closing quote so the error can name the extra characters
+                let source = self.source;
+                let extra_start = self.offset();
+                while let Some(&(idx, ch)) = self.chars.peek() {
+                    match ch {
+                        '\'' => {
+                            self.chars.next();
+                            return Err(LumeError::Lexical {
+                                msg: format!(
+                                    "character literal must contain exactly one character, found extra '{}'; use a string literal \"{}\" instead",
+                                    &source[extra_start..idx],
+                                    &source[content_start..idx]
+                                ),
+                                span: self.span_from(self.token_start),
+                            });
+                        }
+                        '\n' => break,
+                        '\\' => {
+                            // Don't let an escaped quote end the scan, but still stop at
+                            // the end of the line
+                            self.chars.next();
+                            if self.peek() != Some('\n') {
+                                self.chars.next();
+                            }
+                        }
+                        _ => {
+                            self.chars.next();
+                        }
+                    }
                 }
-                self.chars.next(); // Consume closing quote
-                c
+                Err(LumeError::Lexical {
+                    msg: "unterminated character literal".into(),
+                    span: self.span_from(self.token_start),
+                })
             }
-        };
-
-        let end = if let Some((next_idx, _)) = self.chars.peek() {
-            *next_idx
-        } else {
-            self.source.len()
-        };
-
-        Ok((Token::PrefixedChar(prefix, ch), end))
+        }
     }
 
     // --- Comment processing ---
-    // Skip line comments
-    fn skip_line_comment(&mut self) {
-        while let Some((_, ch)) = self.chars.next() {
-            if ch == '\n' {
+    // Skip line comments up to (not including) the line ending, returning the comment
+    // text without the leading slashes
+    fn read_line_comment(&mut self) -> String {
+        let source = self.source;
+        let mut comment = String::new();
+        while let Some(ch) = self.peek() {
+            let rest = &source[self.offset()..];
+            if rest.starts_with('\n') || rest.starts_with("\r\n") {
                 break;
             }
+            self.chars.next();
+            comment.push(ch);
         }
+        comment
+    }
+
+    // Apply a `// @edition: <name>` pragma from the file's leading comments
+    fn apply_pragma(&mut self, comment: &str, start: usize) -> Result<(), LumeError> {
+        if let Some(name) = comment.trim().strip_prefix("@edition:") {
+            if self.edition_pragma {
+                return Err(LumeError::Lexical {
+                    msg: "duplicate '@edition:' pragma".into(),
+                    span: self.span_from(start),
+                });
+            }
+            self.edition_pragma = true;
+            let name = name.trim();
+            self.edition = Edition::from_name(name).ok_or_else(|| LumeError::Lexical {
+                msg: format!("unknown edition '{}'", name),
+                span: self.span_from(start),
+            })?;
+        }
+        Ok(())
     }
 
     // Skip block comments, handle nested comments
@@ -735,22 +826,18 @@ impl<'a> Lexer<'a> {
         let mut depth = 1;
         while depth > 0 {
             match self.chars.next() {
-                Some((_, '*')) => {
-                    if self.peek() == Some('/') {
-                        self.chars.next();
-                        depth -= 1;
-                    }
+                Some((_, '*')) if self.peek() == Some('/') => {
+                    self.chars.next();
+                    depth -= 1;
                 }
-                Some((_, '/')) => {
-                    if self.peek() == Some('*') {
-                        self.chars.next();
-                        depth += 1;
-                    }
+                Some((_, '/')) if self.peek() == Some('*') => {
+                    self.chars.next();
+                    depth += 1;
                 }
                 None => {
                     return Err(LumeError::Lexical {
                         msg: "unterminated block comment".into(),
-                        span: self.span(0, self.source.len()),
+                        span: self.span(self.token_start, self.source.len()),
                     });
                 }
                 _ => {}
@@ -760,6 +847,16 @@ impl<'a> Lexer<'a> {
     }
 }
 
+// Helper function: whether a character can continue an identifier
+fn is_ident_continue(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch > '\u{7F}'
+}
+
+// Helper function: whether the digits spell 9223372036854775808, the magnitude of i64::MIN
+fn is_i64_min_magnitude(s: &str, base: u32) -> bool {
+    u64::from_str_radix(s, base) == Ok(i64::MIN.unsigned_abs())
+}
+
 // Helper function: parse integer from string with specified base, return i64
 fn i64_from_radix(s: &str, base: u32) -> Result<i64, ()> {
     if s.is_empty() {
@@ -781,6 +878,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_numbers() {
         let cases = vec![
             ("42", Token::Int(42)),
@@ -799,6 +897,302 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_edition_pragma() {
+        let tokens = lex("#!/usr/bin/env lume\n// @edition: 2025\nlet x;", "test").unwrap();
+        assert!(matches!(tokens[0].0, Token::Let));
+        match lex("// @edition: 1999\r\nlet x;", "test") {
+            Err(LumeError::Lexical { span, .. }) => assert_eq!((span.start, span.end), (0, 17)),
+            other => panic!("expected lexical error, got {:?}", other),
+        }
+        let input = "// @edition: 2026\n// @edition: 2025\nlet x;";
+        match lex(input, "test") {
+            Err(LumeError::Lexical { msg, span }) => {
+                assert!(msg.contains("duplicate"), "{}", msg);
+                assert_eq!(&input[span.start..span.end], "// @edition: 2025");
+            }
+            other => panic!("expected lexical error, got {:?}", other),
+        }
+        // Doc comments are never pragmas
+        assert!(lex("/// @edition: 1999\nlet x;", "test").is_ok());
+        // Pragma after the first token is just a comment
+        assert!(lex("let x; // @edition: 1999", "test").is_ok());
+    }
+
+    #[test]
+    fn test_shebang_spans() {
+        let input = "#!/usr/bin/env lume\nlet x = 1;";
+        let tokens = lex(input, "test").unwrap();
+        let span = &tokens[0].1;
+        assert_eq!(&input[span.start..span.end], "let");
+    }
+
+    // Deterministic xorshift generator yielding values in 0..n, so property test
+    // failures are reproducible
+    fn xorshift(seed: u64) -> impl FnMut(usize) -> usize {
+        let mut state = seed;
+        move |n| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n as u64) as usize
+        }
+    }
+
+    // Property: every token's span slices to exactly the text it was lexed from
+    #[test]
+    fn test_token_spans_match_source() {
+        let pieces = [
+            "let",
+            "café",
+            "x",
+            "_y2",
+            "🦀ident",
+            "42",
+            "0xFF",
+            "1_000",
+            "3.5",
+            "1e5",
+            "\"héllo\"",
+            "\"\"",
+            "\"a\\n\"",
+            "\"中文\"",
+            "'中'",
+            "'\\n'",
+            "'\\u{1F600}'",
+            "'static",
+            "r\"raw\\n\"",
+            "sql'x'",
+            "==",
+            "=>",
+            "->",
+            "<<=",
+            ">>",
+            "<=",
+            "!=",
+            "+",
+            "%=",
+            "(",
+            ")",
+            "{",
+            "}",
+            ";",
+            ".",
+            "..",
+            "..=",
+            "...",
+            "@",
+            "?",
+        ];
+        let separators = [" ", "\n", "\t", " /* é */ ", " // 注释\n"];
+        let mut next = xorshift(0x5eed);
+        for _ in 0..200 {
+            let mut input = String::new();
+            let mut expected = Vec::new();
+            for _ in 0..next(12) + 1 {
+                let piece = pieces[next(pieces.len())];
+                expected.push(piece);
+                input.push_str(piece);
+                input.push_str(separators[next(separators.len())]);
+            }
+            let tokens = lex(&input, "test").unwrap();
+            let actual: Vec<&str> = tokens
+                .iter()
+                .filter(|(tok, _)| !matches!(tok, Token::Eof))
+                .map(|(_, span)| &input[span.start..span.end])
+                .collect();
+            assert_eq!(actual, expected, "failed for {:?}", input);
+        }
+    }
+
+    // Property: arbitrary input never panics, and successful output is well-formed
+    #[test]
+    fn test_arbitrary_input_never_panics() {
+        let alphabet: Vec<char> = "'\"\\/*!&|=<>-+.0123456789xXbBoOeE_u{}ra中é🦀 \n\t#@"
+            .chars()
+            .collect();
+        let mut next = xorshift(0xf022);
+        for round in 0..4000 {
+            let len = next(48);
+            let input: String = if round % 2 == 0 {
+                (0..len).map(|_| alphabet[next(alphabet.len())]).collect()
+            } else {
+                let bytes: Vec<u8> = (0..len).map(|_| next(256) as u8).collect();
+                String::from_utf8_lossy(&bytes).into_owned()
+            };
+            if let Ok(tokens) = lex(&input, "test") {
+                assert!(matches!(tokens.last(), Some((Token::Eof, _))));
+                for (_, span) in &tokens {
+                    assert!(span.start <= span.end && span.end <= input.len());
+                    assert!(input.is_char_boundary(span.start) && input.is_char_boundary(span.end));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_i64_min_literal() {
+        let kinds = |input| -> Vec<Token> {
+            lex(input, "test")
+                .unwrap()
+                .into_iter()
+                .map(|(tok, _)| tok)
+                .collect()
+        };
+        // The magnitude of i64::MIN lexes like any other literal, with the minus kept
+        // separate, so the parser sees the same shape as for -9223372036854775807
+        let input = "-9223372036854775808";
+        let tokens = lex(input, "test").unwrap();
+        assert_eq!(tokens[0].0, Token::Minus);
+        assert_eq!(tokens[1].0, Token::IntMinMagnitude);
+        assert_eq!(&input[tokens[1].1.start..tokens[1].1.end], &input[1..]);
+        assert_eq!(
+            kinds("-9223372036854775808.abs()")[..3],
+            [Token::Minus, Token::IntMinMagnitude, Token::Dot]
+        );
+        assert_eq!(
+            kinds("-9223372036854775807.abs()")[..3],
+            [Token::Minus, Token::Int(i64::MAX), Token::Dot]
+        );
+        assert_eq!(kinds("-0x8000000000000000")[1], Token::IntMinMagnitude);
+        // Binary minus is not special-cased by the lexer either
+        assert_eq!(kinds("a - 9223372036854775808")[2], Token::IntMinMagnitude);
+
+        assert!(lex("9223372036854775809", "test").is_err());
+        assert!(lex("-9223372036854775809", "test").is_err());
+    }
+
+    #[test]
+    fn test_float_overflow() {
+        assert!(lex("1e999", "test").is_err());
+        assert!(lex("1.5e308_9", "test").is_err());
+        let tokens = lex("1.7976931348623157e308", "test").unwrap();
+        assert_eq!(tokens[0].0, Token::Float(f64::MAX));
+    }
+
+    #[test]
+    fn test_keyword_table_matches_lexer() {
+        for (i, (kw, tok)) in token::KEYWORDS.iter().enumerate() {
+            let tokens = lex_with_edition(kw, "test", Edition::LATEST).unwrap();
+            assert_eq!(&tokens[0].0, tok, "{} is not lexed as its keyword", kw);
+            assert_eq!(token::fixed_text(tok), Some(*kw));
+            assert!(
+                !token::KEYWORDS[i + 1..]
+                    .iter()
+                    .any(|(other, other_tok)| other == kw || other_tok == tok),
+                "{} listed twice",
+                kw
+            );
+        }
+        for (kw, _) in token::EDITION_KEYWORDS {
+            assert!(
+                token::KEYWORDS.iter().any(|(other, _)| other == kw),
+                "{}",
+                kw
+            );
+        }
+    }
+
+    #[test]
+    fn test_fixed_text_round_trips() {
+        let input = "+ - * / = == != % %= < > <= >= & | ^ ~ << >> += -= *= /= &= |= ^= <<= >>= \
+                     ( ) { } [ ] , ; . .. ..= ... : -> @ ? =>";
+        let tokens = lex(input, "test").unwrap();
+        for (tok, span) in tokens.iter().filter(|(tok, _)| *tok != Token::Eof) {
+            assert_eq!(token::fixed_text(tok), Some(&input[span.start..span.end]));
+        }
+    }
+
+    #[test]
+    fn test_suggest_keyword() {
+        use token::suggest_keyword;
+        assert_eq!(suggest_keyword("fnuc", Edition::LATEST), Some("func"));
+        assert_eq!(suggest_keyword("retrun", Edition::LATEST), Some("return"));
+        assert_eq!(suggest_keyword("improt", Edition::LATEST), Some("import"));
+        assert_eq!(suggest_keyword("clas", Edition::LATEST), Some("class"));
+        assert_eq!(suggest_keyword("return", Edition::LATEST), None);
+        assert_eq!(suggest_keyword("counter", Edition::LATEST), None);
+        assert_eq!(suggest_keyword("x", Edition::LATEST), None);
+        assert_eq!(suggest_keyword("i", Edition::LATEST), None);
+        assert_eq!(suggest_keyword("a", Edition::LATEST), None);
+        assert_eq!(suggest_keyword("In", Edition::LATEST), Some("in"));
+        assert_eq!(suggest_keyword("Return", Edition::LATEST), Some("return"));
+        assert_eq!(suggest_keyword("In", Edition::E2025), None);
+    }
+
+    #[test]
+    fn test_in_keyword_edition() {
+        let tokens = lex_with_edition("x not in xs", "test", Edition::E2026).unwrap();
+        assert_eq!(tokens[1].0, Token::Not);
+        assert_eq!(tokens[2].0, Token::In);
+        // `in` stays an identifier in the 2025 edition
+        let tokens = lex("let in = 1;", "test").unwrap();
+        assert_eq!(tokens[1].0, Token::Ident("in".into()));
+        let tokens = lex("// @edition: 2026\nx in xs", "test").unwrap();
+        assert_eq!(tokens[1].0, Token::In);
+        let tokens = lex_with_edition("a orelse 0", "test", Edition::E2026).unwrap();
+        assert_eq!(tokens[1].0, Token::OrElse);
+        assert_eq!(
+            token::reserved_after("in", Edition::E2025),
+            Some(Edition::E2026)
+        );
+        assert_eq!(token::reserved_after("in", Edition::E2026), None);
+    }
+
+    #[test]
+    fn test_cascade_dots() {
+        let tokens = lex("obj..setA(1)..setB(2).build()", "test").unwrap();
+        assert_eq!(tokens[1].0, Token::DotDot);
+        assert_eq!(tokens[6].0, Token::DotDot);
+        assert_eq!(tokens[11].0, Token::Dot);
+        // A number followed by `..` is not a float
+        let tokens = lex("1..x", "test").unwrap();
+        assert_eq!(tokens[0].0, Token::Int(1));
+        assert_eq!(tokens[1].0, Token::DotDot);
+    }
+
+    #[test]
+    fn test_inclusive_range() {
+        let tokens = lex("case 0..=9 => 1;", "test").unwrap();
+        assert_eq!(tokens[1].0, Token::Int(0));
+        assert_eq!(tokens[2].0, Token::DotDotEq);
+        assert_eq!(tokens[3].0, Token::Int(9));
+        assert_eq!(tokens[4].0, Token::FatArrow);
+    }
+
+    #[test]
+    fn test_spread() {
+        let tokens = lex("[1, ...rest] f(...args)", "test").unwrap();
+        assert_eq!(tokens[3].0, Token::Ellipsis);
+        assert_eq!(tokens[4].0, Token::Ident("rest".into()));
+        assert_eq!(tokens[8].0, Token::Ellipsis);
+        // Four dots are a spread followed by a dot
+        let tokens = lex("....", "test").unwrap();
+        assert_eq!(tokens[0].0, Token::Ellipsis);
+        assert_eq!(tokens[1].0, Token::Dot);
+    }
+
+    #[test]
+    fn test_labeled_break() {
+        let input = "let x = 'outer: { break 'outer 5; };";
+        let tokens = lex_with_edition(input, "test", Edition::E2026).unwrap();
+        assert_eq!(tokens[3].0, Token::Lifetime("outer".into()));
+        assert_eq!(tokens[4].0, Token::Colon);
+        assert_eq!(tokens[6].0, Token::Break);
+        assert_eq!(tokens[7].0, Token::Lifetime("outer".into()));
+        assert_eq!(tokens[8].0, Token::Int(5));
+    }
+
+    #[test]
+    fn test_generator_keywords() {
+        let input = "func gen() yields int { yield 1; }";
+        let tokens = lex_with_edition(input, "test", Edition::E2026).unwrap();
+        assert_eq!(tokens[4].0, Token::Yields);
+        assert_eq!(tokens[7].0, Token::Yield);
+        let tokens = lex(input, "test").unwrap();
+        assert_eq!(tokens[4].0, Token::Ident("yields".into()));
+    }
+
     #[test]
     fn test_prefixed_string() {
         let input = r#"r"hello\nworld" sql"SELECT * FROM users""#;
@@ -829,6 +1223,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ascii_escapes() {
+        let tokens = lex(r#"'\0' '\x41' '\x7F' "a\x42\0""#, "test").unwrap();
+        assert_eq!(tokens[0].0, Token::Char('\0'));
+        assert_eq!(tokens[1].0, Token::Char('A'));
+        assert_eq!(tokens[2].0, Token::Char('\x7F'));
+        assert_eq!(tokens[3].0, Token::Str("aB\0".into()));
+        assert!(lex(r"'\x80'", "test").is_err());
+        assert!(lex(r"'\x4'", "test").is_err());
+        assert!(lex(r"'\xg0'", "test").is_err());
+    }
+
+    #[test]
+    fn test_line_continuation() {
+        let tokens = lex("\"one \\\n     two \\\r\n\tthree\"", "test").unwrap();
+        assert_eq!(tokens[0].0, Token::Str("one two three".into()));
+        // Raw strings keep the backslash and newline
+        let tokens = lex("r\"a\\\nb\"", "test").unwrap();
+        assert_eq!(tokens[0].0, Token::PrefixedStr("r".into(), "a\\\nb".into()));
+        assert!(lex("'\\\n'", "test").is_err());
+    }
+
+    #[test]
+    fn test_hex_escape_out_of_range() {
+        match lex(r"'\xFF'", "test") {
+            Err(LumeError::Lexical { msg, span }) => {
+                assert!(msg.contains("character literal"), "{}", msg);
+                assert!(msg.contains(r"'\u{FF}'"), "{}", msg);
+                assert_eq!((span.start, span.end), (1, 5));
+            }
+            other => panic!("expected lexical error, got {:?}", other),
+        }
+        match lex(r#""\x80""#, "test") {
+            Err(LumeError::Lexical { msg, .. }) => {
+                assert!(msg.contains("string literal"), "{}", msg)
+            }
+            other => panic!("expected lexical error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_multi_char_literal_error() {
+        let input = "let c = 'ab';";
+        match lex(input, "test") {
+            Err(LumeError::Lexical { msg, span }) => {
+                assert!(msg.contains("found extra 'b'"), "{}", msg);
+                assert!(msg.contains("\"ab\""), "{}", msg);
+                assert_eq!(&input[span.start..span.end], "'ab'");
+            }
+            other => panic!("expected lexical error, got {:?}", other),
+        }
+        assert!(lex("'中文'", "test").is_err());
+        // Escapes are shown as written in both the extra text and the suggestion
+        match lex(r"'\n\t'", "test") {
+            Err(LumeError::Lexical { msg, .. }) => {
+                assert!(
+                    msg.contains(r#"found extra '\t'; use a string literal "\n\t""#),
+                    "{}",
+                    msg
+                );
+            }
+            other => panic!("expected lexical error, got {:?}", other),
+        }
+        // An identifier run followed by something other than a quote is still a char
+        // literal when the line closes it
+        for (input, extra) in [(r"'a\n'", r"\n"), ("'a\"'", "\"")] {
+            match lex(input, "test") {
+                Err(LumeError::Lexical { msg, span }) => {
+                    assert!(msg.contains(&format!("found extra '{}'", extra)), "{}", msg);
+                    assert_eq!(&input[span.start..span.end], input);
+                }
+                other => panic!("expected lexical error, got {:?}", other),
+            }
+        }
+        // A backslash before the newline does not carry the scan onto the next line
+        match lex("'5\\\nx'", "test") {
+            Err(LumeError::Lexical { msg, span }) => {
+                assert!(msg.contains("unterminated"), "{}", msg);
+                assert_eq!((span.start, span.end), (0, 3));
+            }
+            other => panic!("expected lexical error, got {:?}", other),
+        }
+        // Lifetimes are unaffected
+        let tokens = lex("&'a T", "test").unwrap();
+        assert_eq!(tokens[1].0, Token::Lifetime("a".into()));
+        let tokens = lex("f<'a, 'b>(x: &'a T)", "test").unwrap();
+        assert_eq!(tokens[2].0, Token::Lifetime("a".into()));
+        assert_eq!(tokens[4].0, Token::Lifetime("b".into()));
+    }
+
     #[test]
     fn test_prefixed_char() {
         let cases = vec![
@@ -901,6 +1385,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_comprehensive_lexer() {
         let input = r#"#!/usr/bin/env lume
 // This is a comprehensive test for the Lume lexer
@@ -1,5 +1,7 @@
 // src/lexer/token.rs
 
+use crate::edition::Edition;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     // Keywords
@@ -15,6 +17,9 @@ pub enum Token {
     Throws,
     Recover,
     Return,
+    Break,
+    Yield,
+    Yields,
     Import,
     Export,
     Default,
@@ -27,6 +32,8 @@ pub enum Token {
     With,
     Type,
     Is,
+    In,
+    OrElse,
     Unsafe,
     Extern,
     Async,
@@ -39,6 +46,10 @@ pub enum Token {
 
     // Literals
     Int(i64),
+    // 9223372036854775808, the magnitude of i64::MIN. The lexer keeps `-` a separate
+    // token and accepts this anywhere; the parser must fold a unary minus applied to
+    // it into Int(i64::MIN) and report "integer literal too large" everywhere else.
+    IntMinMagnitude,
     Float(f64),
     Str(String),
     PrefixedStr(String, String),
@@ -97,6 +108,11 @@ pub enum Token {
     Comma,
     Semicolon,
     Dot,
+    // .. (cascade `obj..setA(1)..setB(2)`, exclusive range `0..10`,
+    // struct update `Point { x = 1, ..other }`); the parser tells them apart by position
+    DotDot,
+    DotDotEq, // ..= (inclusive range pattern: `case 0..=9`)
+    Ellipsis, // ... (spread: `[1, ...rest]`, `f(...args)`)
     Colon,
     Arrow, // ->
     At,
@@ -107,43 +123,186 @@ pub enum Token {
     Eof,
 }
 
-pub fn keyword_or_ident(ident: &str) -> Token {
-    match ident {
-        "let" => Token::Let,
-        "mut" => Token::Mut,
-        "func" => Token::Func,
-        "if" => Token::If,
-        "else" => Token::Else,
-        "match" => Token::Match,
-        "case" => Token::Case,
-        "on" => Token::On,
-        "own" => Token::Own,
-        "throws" => Token::Throws,
-        "recover" => Token::Recover,
-        "return" => Token::Return,
-        "import" => Token::Import,
-        "export" => Token::Export,
-        "default" => Token::Default,
-        "as" => Token::As,
-        "excluding" => Token::Excluding,
-        "from" => Token::From,
-        "enum" => Token::Enum,
-        "class" => Token::Class,
-        "macro" => Token::Macro,
-        "with" => Token::With,
-        "type" => Token::Type,
-        "is" => Token::Is,
-        "and" => Token::And,
-        "or" => Token::Or,
-        "not" => Token::Not,
-        "true" => Token::Bool(true),
-        "false" => Token::Bool(false),
-        "unsafe" => Token::Unsafe,
-        "extern" => Token::Extern,
-        "async" => Token::Async,
-        "await" => Token::Await,
-        "spawn" => Token::Spawn,
-        "effect" => Token::Effect,
-        _ => Token::Ident(ident.into()),
+// Every reserved word with the token it lexes as, including word operators and
+// boolean literals. `keyword_or_ident`, `suggest_keyword` and `fixed_text` all read
+// this table.
+pub const KEYWORDS: &[(&str, Token)] = &[
+    ("let", Token::Let),
+    ("mut", Token::Mut),
+    ("func", Token::Func),
+    ("if", Token::If),
+    ("else", Token::Else),
+    ("match", Token::Match),
+    ("case", Token::Case),
+    ("on", Token::On),
+    ("own", Token::Own),
+    ("throws", Token::Throws),
+    ("recover", Token::Recover),
+    ("return", Token::Return),
+    ("break", Token::Break),
+    ("yield", Token::Yield),
+    ("yields", Token::Yields),
+    ("import", Token::Import),
+    ("export", Token::Export),
+    ("default", Token::Default),
+    ("as", Token::As),
+    ("excluding", Token::Excluding),
+    ("from", Token::From),
+    ("enum", Token::Enum),
+    ("class", Token::Class),
+    ("macro", Token::Macro),
+    ("with", Token::With),
+    ("type", Token::Type),
+    ("is", Token::Is),
+    ("in", Token::In),
+    ("orelse", Token::OrElse),
+    ("and", Token::And),
+    ("or", Token::Or),
+    ("not", Token::Not),
+    ("true", Token::Bool(true)),
+    ("false", Token::Bool(false)),
+    ("unsafe", Token::Unsafe),
+    ("extern", Token::Extern),
+    ("async", Token::Async),
+    ("await", Token::Await),
+    ("spawn", Token::Spawn),
+    ("effect", Token::Effect),
+];
+
+// Keywords introduced after the first edition, with the edition that reserves them
+pub const EDITION_KEYWORDS: &[(&str, Edition)] = &[
+    ("in", Edition::E2026),
+    ("orelse", Edition::E2026),
+    ("break", Edition::E2026),
+    ("yield", Edition::E2026),
+    ("yields", Edition::E2026),
+];
+
+// Edition in which `ident` becomes a keyword, if that is later than `edition`.
+// Used to warn about identifiers that will break when migrating to a newer edition.
+pub fn reserved_after(ident: &str, edition: Edition) -> Option<Edition> {
+    EDITION_KEYWORDS
+        .iter()
+        .find(|&&(kw, since)| kw == ident && since > edition)
+        .map(|&(_, since)| since)
+}
+
+pub fn keyword_or_ident(ident: &str, edition: Edition) -> Token {
+    if reserved_after(ident, edition).is_some() {
+        return Token::Ident(ident.into());
+    }
+    KEYWORDS
+        .iter()
+        .find(|&&(kw, _)| kw == ident)
+        .map_or_else(|| Token::Ident(ident.into()), |(_, token)| token.clone())
+}
+
+// Closest keyword to a misspelled identifier (e.g. `retrun` -> `return`), for
+// "did you mean" hints. Only keywords of `edition` are offered.
+pub fn suggest_keyword(ident: &str, edition: Edition) -> Option<&'static str> {
+    if KEYWORDS.iter().any(|&(kw, _)| kw == ident) {
+        return None;
+    }
+    let candidates = KEYWORDS
+        .iter()
+        .map(|&(kw, _)| kw)
+        .filter(|kw| reserved_after(kw, edition).is_none());
+    // A keyword in the wrong case (`In`, `Return`) beats any edit-distance guess
+    let lower = ident.to_lowercase();
+    if let Some(kw) = candidates.clone().find(|&kw| kw == lower) {
+        return Some(kw);
+    }
+    // One- and two-letter names are within one edit of too many keywords to guess
+    if ident.chars().count() < 3 {
+        return None;
+    }
+    // Allow one edit for short words, roughly one per three characters for longer ones
+    let max_distance = (ident.chars().count() / 3).max(1);
+    candidates
+        .map(|kw| (edit_distance(ident, kw), kw))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, kw)| kw)
+}
+
+// Optimal string alignment distance: Levenshtein plus adjacent transpositions,
+// so `fnuc` is one edit away from `func`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev_prev = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (prev[j] + 1).min(row[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(prev_prev[j - 2] + 1);
+            }
+        }
+        prev_prev = std::mem::replace(&mut prev, row);
+    }
+    prev[b.len()]
+}
+
+// Source spelling of tokens that always look the same, None for literals,
+// identifiers, lifetimes and Eof
+pub fn fixed_text(token: &Token) -> Option<&'static str> {
+    if let Some(&(kw, _)) = KEYWORDS.iter().find(|(_, tok)| tok == token) {
+        return Some(kw);
     }
+    let text = match token {
+        Token::Plus => "+",
+        Token::Minus => "-",
+        Token::Star => "*",
+        Token::Slash => "/",
+        Token::Eq => "=",
+        Token::EqEq => "==",
+        Token::Neq => "!=",
+        Token::Percent => "%",
+        Token::PercentEq => "%=",
+        Token::Lt => "<",
+        Token::Gt => ">",
+        Token::Le => "<=",
+        Token::Ge => ">=",
+        Token::And => "and",
+        Token::Or => "or",
+        Token::Not => "not",
+        Token::Amp => "&",
+        Token::Pipe => "|",
+        Token::Caret => "^",
+        Token::Tilde => "~",
+        Token::Shl => "<<",
+        Token::Shr => ">>",
+        Token::PlusEq => "+=",
+        Token::MinusEq => "-=",
+        Token::StarEq => "*=",
+        Token::SlashEq => "/=",
+        Token::AmpEq => "&=",
+        Token::PipeEq => "|=",
+        Token::CaretEq => "^=",
+        Token::ShlEq => "<<=",
+        Token::ShrEq => ">>=",
+        Token::LParen => "(",
+        Token::RParen => ")",
+        Token::LBrace => "{",
+        Token::RBrace => "}",
+        Token::LBracket => "[",
+        Token::RBracket => "]",
+        Token::Comma => ",",
+        Token::Semicolon => ";",
+        Token::Dot => ".",
+        Token::DotDot => "..",
+        Token::DotDotEq => "..=",
+        Token::Ellipsis => "...",
+        Token::Colon => ":",
+        Token::Arrow => "->",
+        Token::At => "@",
+        Token::Question => "?",
+        Token::FatArrow => "=>",
+        // Keywords are found in KEYWORDS above
+        _ => return None,
+    };
+    Some(text)
 }
@@ -1,3 +1,4 @@
+pub mod edition;
 pub mod error;
 pub mod span;
 
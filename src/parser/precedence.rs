@@ -0,0 +1,313 @@
+// src/parser/precedence.rs
+// Operator precedence and associativity as data, shared by the parser's Pratt loop
+// and by tooling (formatter, docs) that needs to decide on parenthesization.
+
+use crate::lexer::{Token, token::fixed_text};
+
+// Precedence levels, loosest first; a higher level binds tighter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Precedence {
+    Assign,   // = += -= ...
+    Or,       // or
+    And,      // and
+    Not,      // not (prefix, looser than comparisons: `not a == b` is `not (a == b)`)
+    Compare,  // == != < > <= >= is in (and `not in`)
+    Coalesce, // orelse: `a orelse b == c` is `(a orelse b) == c`
+    BitOr,    // |
+    BitXor,   // ^
+    BitAnd,   // &
+    Shift,    // << >>
+    Sum,      // + -
+    Product,  // * / %
+    Prefix,   // - ~ & own await spawn
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Assoc {
+    Left,
+    Right,
+    // Chaining is a syntax error, e.g. `a < b < c`
+    None,
+}
+
+// Every binary operator with its precedence and associativity
+pub const BINARY_OPERATORS: &[(Token, Precedence, Assoc)] = &[
+    (Token::Eq, Precedence::Assign, Assoc::Right),
+    (Token::PlusEq, Precedence::Assign, Assoc::Right),
+    (Token::MinusEq, Precedence::Assign, Assoc::Right),
+    (Token::StarEq, Precedence::Assign, Assoc::Right),
+    (Token::SlashEq, Precedence::Assign, Assoc::Right),
+    (Token::PercentEq, Precedence::Assign, Assoc::Right),
+    (Token::AmpEq, Precedence::Assign, Assoc::Right),
+    (Token::PipeEq, Precedence::Assign, Assoc::Right),
+    (Token::CaretEq, Precedence::Assign, Assoc::Right),
+    (Token::ShlEq, Precedence::Assign, Assoc::Right),
+    (Token::ShrEq, Precedence::Assign, Assoc::Right),
+    (Token::Or, Precedence::Or, Assoc::Left),
+    (Token::And, Precedence::And, Assoc::Left),
+    (Token::EqEq, Precedence::Compare, Assoc::None),
+    (Token::Neq, Precedence::Compare, Assoc::None),
+    (Token::Lt, Precedence::Compare, Assoc::None),
+    (Token::Gt, Precedence::Compare, Assoc::None),
+    (Token::Le, Precedence::Compare, Assoc::None),
+    (Token::Ge, Precedence::Compare, Assoc::None),
+    (Token::Is, Precedence::Compare, Assoc::None),
+    // `a not in b` shares this entry: in infix position the parser reads `not in` as one operator
+    (Token::In, Precedence::Compare, Assoc::None),
+    // Right-associative so `a orelse b orelse c` tries each fallback in turn
+    (Token::OrElse, Precedence::Coalesce, Assoc::Right),
+    (Token::Pipe, Precedence::BitOr, Assoc::Left),
+    (Token::Caret, Precedence::BitXor, Assoc::Left),
+    (Token::Amp, Precedence::BitAnd, Assoc::Left),
+    (Token::Shl, Precedence::Shift, Assoc::Left),
+    (Token::Shr, Precedence::Shift, Assoc::Left),
+    (Token::Plus, Precedence::Sum, Assoc::Left),
+    (Token::Minus, Precedence::Sum, Assoc::Left),
+    (Token::Star, Precedence::Product, Assoc::Left),
+    (Token::Slash, Precedence::Product, Assoc::Left),
+    (Token::Percent, Precedence::Product, Assoc::Left),
+];
+
+// Every prefix operator with the precedence of its operand
+pub const PREFIX_OPERATORS: &[(Token, Precedence)] = &[
+    (Token::Not, Precedence::Not),
+    (Token::Minus, Precedence::Prefix),
+    (Token::Tilde, Precedence::Prefix),
+    (Token::Amp, Precedence::Prefix),
+    (Token::Own, Precedence::Prefix),
+    // `await (t1, t2)` parses as `await` applied to a tuple; waiting on all of them is
+    // resolved after parsing
+    (Token::Await, Precedence::Prefix),
+    (Token::Spawn, Precedence::Prefix),
+];
+
+pub fn binary_operator(token: &Token) -> Option<(Precedence, Assoc)> {
+    BINARY_OPERATORS
+        .iter()
+        .find(|(op, _, _)| op == token)
+        .map(|&(_, prec, assoc)| (prec, assoc))
+}
+
+pub fn prefix_operator(token: &Token) -> Option<Precedence> {
+    PREFIX_OPERATORS
+        .iter()
+        .find(|(op, _)| op == token)
+        .map(|&(_, prec)| prec)
+}
+
+// The operator at the root of an expression, in the role it plays there: `-` and `&`
+// are both prefix and binary, so the token alone cannot say which is meant
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operator {
+    // Prefix operator, with the precedence of its operand
+    Prefix(Precedence),
+    Binary(Precedence, Assoc),
+}
+
+impl Operator {
+    pub fn prefix(token: &Token) -> Option<Operator> {
+        prefix_operator(token).map(Operator::Prefix)
+    }
+
+    pub fn binary(token: &Token) -> Option<Operator> {
+        binary_operator(token).map(|(prec, assoc)| Operator::Binary(prec, assoc))
+    }
+
+    pub fn precedence(self) -> Precedence {
+        match self {
+            Operator::Prefix(prec) | Operator::Binary(prec, _) => prec,
+        }
+    }
+}
+
+// Whether an `inner` expression needs parentheses as an operand of `outer`;
+// `is_right_operand` picks the side when `outer` is binary
+pub fn needs_parens(outer: Operator, inner: Operator, is_right_operand: bool) -> bool {
+    let inner_prec = inner.precedence();
+    let (outer_prec, assoc) = match outer {
+        // `not a == b` is `not (a == b)`, but `-(a * b)` needs them
+        Operator::Prefix(operand) => return inner_prec < operand,
+        Operator::Binary(prec, assoc) => (prec, assoc),
+    };
+    if inner_prec != outer_prec {
+        return inner_prec < outer_prec;
+    }
+    match assoc {
+        Assoc::Left => is_right_operand,
+        Assoc::Right => !is_right_operand,
+        Assoc::None => true,
+    }
+}
+
+// Rule name used for a level in the generated grammar
+fn rule_name(prec: Precedence) -> String {
+    format!("{:?}", prec).to_lowercase()
+}
+
+// Render the expression grammar implied by the tables above as EBNF, one rule per
+// precedence level. The `postfix` rule (calls, fields, `?`) and everything below it
+// are left to the parser's own documentation.
+pub fn expression_ebnf() -> String {
+    let mut levels: Vec<Precedence> = BINARY_OPERATORS
+        .iter()
+        .map(|&(_, prec, _)| prec)
+        .chain(PREFIX_OPERATORS.iter().map(|&(_, prec)| prec))
+        .collect();
+    levels.sort();
+    levels.dedup();
+
+    let quote = |token: &Token| {
+        let text = fixed_text(token)
+            .unwrap_or_else(|| panic!("operator {:?} has no fixed spelling", token));
+        format!("\"{}\"", text)
+    };
+    let group = |ops: Vec<String>| match ops.len() {
+        1 => ops[0].clone(),
+        _ => format!("( {} )", ops.join(" | ")),
+    };
+
+    let mut out = format!("expression = {} ;\n", rule_name(levels[0]));
+    for (i, &level) in levels.iter().enumerate() {
+        let name = rule_name(level);
+        let next = levels
+            .get(i + 1)
+            .map_or_else(|| "postfix".to_string(), |&prec| rule_name(prec));
+
+        let prefix: Vec<String> = PREFIX_OPERATORS
+            .iter()
+            .filter(|&&(_, prec)| prec == level)
+            .map(|(op, _)| quote(op))
+            .collect();
+        let mut binary = Vec::new();
+        let mut assoc = None;
+        for (op, prec, op_assoc) in BINARY_OPERATORS {
+            if *prec == level {
+                binary.push(quote(op));
+                if *op == Token::In {
+                    binary.push("\"not\" , \"in\"".to_string());
+                }
+                // One rule shape per level, so every operator in it must agree
+                assert!(
+                    assoc.is_none_or(|assoc| assoc == *op_assoc),
+                    "{:?} mixes associativities",
+                    level
+                );
+                assoc = Some(*op_assoc);
+            }
+        }
+        assert!(
+            prefix.is_empty() || binary.is_empty(),
+            "{:?} mixes prefix and binary operators",
+            level
+        );
+
+        let rhs = if !prefix.is_empty() {
+            format!("{} , {} | {}", group(prefix), name, next)
+        } else {
+            let ops = group(binary);
+            match assoc.unwrap_or(Assoc::Left) {
+                Assoc::Left => format!("{} , {{ {} , {} }}", next, ops, next),
+                Assoc::Right => format!("{} , [ {} , {} ]", next, ops, name),
+                Assoc::None => format!("{} , [ {} , {} ]", next, ops, next),
+            }
+        };
+        out.push_str(&format!("{} = {} ;\n", name, rhs));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operators_listed_once() {
+        for (i, (op, _, _)) in BINARY_OPERATORS.iter().enumerate() {
+            assert!(
+                !BINARY_OPERATORS[i + 1..]
+                    .iter()
+                    .any(|(other, _, _)| other == op),
+                "{:?} listed twice",
+                op
+            );
+        }
+    }
+
+    // expression_ebnf() renders one rule shape per level, and asserts this holds
+    #[test]
+    fn test_levels_are_uniform() {
+        for &(_, level, assoc) in BINARY_OPERATORS {
+            assert!(
+                PREFIX_OPERATORS.iter().all(|&(_, prec)| prec != level),
+                "{:?} has both prefix and binary operators",
+                level
+            );
+            assert!(
+                BINARY_OPERATORS
+                    .iter()
+                    .all(|&(_, prec, other)| prec != level || other == assoc),
+                "{:?} mixes associativities",
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn test_expression_ebnf() {
+        let grammar = expression_ebnf();
+        assert!(grammar.starts_with("expression = assign ;\n"));
+        assert!(grammar.contains("\nsum = product , { ( \"+\" | \"-\" ) , product } ;\n"));
+        assert!(grammar.contains("\ncoalesce = bitor , [ \"orelse\" , coalesce ] ;\n"));
+        assert!(grammar.contains("\"not\" , \"in\""));
+        assert!(grammar.contains("\nnot = \"not\" , not | compare ;\n"));
+        assert!(
+            grammar.ends_with(
+                "\nproduct = prefix , { ( \"*\" | \"/\" | \"%\" ) , prefix } ;\n\
+                 prefix = ( \"-\" | \"~\" | \"&\" | \"own\" | \"await\" | \"spawn\" ) , prefix | postfix ;\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_needs_parens() {
+        let bin = |token| Operator::binary(&token).unwrap();
+        let pre = |token| Operator::prefix(&token).unwrap();
+        // (a + b) * c
+        assert!(needs_parens(bin(Token::Star), bin(Token::Plus), false));
+        // a * b + c
+        assert!(!needs_parens(bin(Token::Plus), bin(Token::Star), false));
+        // a - (b - c) vs (a - b) - c
+        assert!(needs_parens(bin(Token::Minus), bin(Token::Minus), true));
+        assert!(!needs_parens(bin(Token::Minus), bin(Token::Minus), false));
+        // a = (b = c) is the natural grouping
+        assert!(!needs_parens(bin(Token::Eq), bin(Token::Eq), true));
+        // (a < b) == c
+        assert!(needs_parens(bin(Token::EqEq), bin(Token::Lt), false));
+        // a & b == c groups as (a & b) == c
+        assert!(!needs_parens(bin(Token::EqEq), bin(Token::Amp), false));
+        // a orelse (b + 1), (a orelse b) == c
+        assert!(!needs_parens(bin(Token::OrElse), bin(Token::Plus), true));
+        assert!(!needs_parens(bin(Token::EqEq), bin(Token::OrElse), false));
+
+        // not (a and b), but not a == b is already not (a == b)
+        assert!(needs_parens(pre(Token::Not), bin(Token::And), true));
+        assert!(!needs_parens(pre(Token::Not), bin(Token::EqEq), true));
+        // (not a) == b, but not a and b is already (not a) and b
+        assert!(needs_parens(bin(Token::EqEq), pre(Token::Not), false));
+        assert!(!needs_parens(bin(Token::And), pre(Token::Not), false));
+        // -(a - b), a - -b, -a * b
+        assert!(needs_parens(pre(Token::Minus), bin(Token::Minus), true));
+        assert!(!needs_parens(bin(Token::Minus), pre(Token::Minus), true));
+        assert!(!needs_parens(bin(Token::Star), pre(Token::Minus), false));
+        // &(a & b), &a & b, - -a
+        assert!(needs_parens(pre(Token::Amp), bin(Token::Amp), true));
+        assert!(!needs_parens(bin(Token::Amp), pre(Token::Amp), false));
+        assert!(!needs_parens(pre(Token::Minus), pre(Token::Minus), true));
+        // -(not a)
+        assert!(needs_parens(pre(Token::Minus), pre(Token::Not), true));
+
+        assert_eq!(prefix_operator(&Token::Not), Some(Precedence::Not));
+        assert_eq!(prefix_operator(&Token::Await), Some(Precedence::Prefix));
+        assert_eq!(binary_operator(&Token::As), None);
+    }
+}